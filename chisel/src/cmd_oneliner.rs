@@ -2,7 +2,14 @@
 //! The main entry point is chisel_oneliner, which uses FromArgs to produce ChiselConfig
 //! from the relevant options passed in the CLI.
 //! Like config-driven mode, it then passes the config to the driver, executes, and writes
-//! output to the specified file (or stdout, if no file is specified).
+//! output to the specified file, or streams it to stdout if no file is specified.
+//! Input may likewise come from stdin by passing `-` (or no file argument at all),
+//! which makes chisel usable as a regular link in a Unix pipeline.
+
+use std::io::{self, Read, Write as _};
+use std::path::{Path, PathBuf};
+
+use tempfile::NamedTempFile;
 
 use crate::config::ChiselConfig;
 use crate::config::FromArgs;
@@ -11,101 +18,509 @@ use crate::fail;
 use crate::logger;
 use crate::options::ChiselFlags;
 
+/// Expands a comma-separated list of paths/globs (as accepted by
+/// `oneliner.file`) into the concrete files to process.
+fn expand_input_files(pattern_list: &str) -> Vec<String> {
+    pattern_list
+        .split(',')
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+        .flat_map(|pattern| match glob::glob(pattern) {
+            Ok(paths) => {
+                let expanded: Vec<String> = paths
+                    .filter_map(Result::ok)
+                    .map(|p| p.to_string_lossy().into_owned())
+                    .collect();
+                if expanded.is_empty() {
+                    // Not a glob (or it matched nothing); treat it as a literal path
+                    // and let the driver report a missing-file error if it's wrong.
+                    vec![pattern.to_string()]
+                } else {
+                    expanded
+                }
+            }
+            Err(_) => vec![pattern.to_string()],
+        })
+        .collect()
+}
+
+/// Derives the default output path for a batch run: `{stem}.chiseled.{ext}`
+/// next to the input file.
+fn templated_output_path(input_file: &str) -> String {
+    let path = std::path::Path::new(input_file);
+    let stem = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_else(|| input_file.to_string());
+    let ext = path
+        .extension()
+        .map(|e| e.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "wasm".to_string());
+    match path.parent() {
+        Some(parent) if parent.as_os_str().len() > 0 => parent
+            .join(format!("{}.chiseled.{}", stem, ext))
+            .to_string_lossy()
+            .into_owned(),
+        _ => format!("{}.chiseled.{}", stem, ext),
+    }
+}
+
+/// The name of the project-wide config file `chisel_oneliner` auto-discovers,
+/// analogous to rustfmt's `rustfmt.toml`.
+const PROJECT_CONFIG_FILE: &str = ".chisel.yml";
+
+/// Walks upward from `start_dir` looking for a [`PROJECT_CONFIG_FILE`],
+/// mirroring rustfmt's `get_toml_path`. Returns the first one found.
+fn find_project_config(start_dir: &Path) -> Option<PathBuf> {
+    let mut dir = Some(start_dir);
+    while let Some(d) = dir {
+        let candidate = d.join(PROJECT_CONFIG_FILE);
+        if candidate.is_file() {
+            return Some(candidate);
+        }
+        dir = d.parent();
+    }
+    None
+}
+
+/// Reads all of stdin into a securely-created temporary file (unpredictable
+/// name, 0600 permissions, created with `O_EXCL`) and returns it. Used when
+/// the caller asked for stdin input (`-` or no file argument at all) so the
+/// rest of the pipeline can keep working with a filesystem path. The file is
+/// removed automatically once the returned handle is dropped, after the
+/// driver has read it.
+fn stage_stdin_to_tempfile() -> NamedTempFile {
+    let mut bytes = Vec::new();
+    io::stdin()
+        .read_to_end(&mut bytes)
+        .unwrap_or_else(|e| fail(1, &format!("failed to read stdin: {}", e)));
+
+    let mut file =
+        NamedTempFile::new().unwrap_or_else(|e| fail(1, &format!("failed to stage stdin input: {}", e)));
+    file.write_all(&bytes)
+        .unwrap_or_else(|e| fail(1, &format!("failed to stage stdin input: {}", e)));
+    file
+}
+
+/// Output noise level, following rustfmt's `Verbosity` model. `Quiet`
+/// suppresses the informational success/no-change messages so scripts
+/// only see real errors on stderr; `Verbose` raises the debug log level.
+#[derive(Clone, Copy, PartialEq)]
+enum Verbosity {
+    Quiet,
+    Normal,
+    Verbose(i32),
+}
+
+impl Verbosity {
+    /// Reads `-q`/`-v` (`util.quiet`/`util.verbose`) from the CLI flags.
+    /// `-v` may be repeated; `occurrences_of` gives the repeat count
+    /// directly, one level of `chisel_debug!` detail per repetition.
+    fn from_flags(flags: &ChiselFlags) -> Verbosity {
+        if flags.occurrences_of("util.quiet") > 0 {
+            return Verbosity::Quiet;
+        }
+        match flags.occurrences_of("util.verbose") {
+            0 => Verbosity::Normal,
+            n => Verbosity::Verbose(n as i32),
+        }
+    }
+
+    fn log_level(self) -> i32 {
+        match self {
+            Verbosity::Quiet | Verbosity::Normal => 0,
+            Verbosity::Verbose(level) => level,
+        }
+    }
+
+    fn is_quiet(self) -> bool {
+        self == Verbosity::Quiet
+    }
+}
+
+/// Output format for the `--emit-report` flag. `Human` prints one plain-text
+/// line per module; the rest are for machine consumption.
+enum ReportFormat {
+    Human,
+    Json,
+    Yaml,
+    Checkstyle,
+}
+
+impl ReportFormat {
+    fn from_flag(value: Option<&str>) -> ReportFormat {
+        match value {
+            None | Some("human") => ReportFormat::Human,
+            Some("json") => ReportFormat::Json,
+            Some("yaml") => ReportFormat::Yaml,
+            Some("checkstyle") => ReportFormat::Checkstyle,
+            Some(other) => fail(1, &format!("unknown --emit-report format: {}", other)),
+        }
+    }
+}
+
+/// One ruleset's outcome, in a shape that's easy to serialize regardless of
+/// the chosen `ReportFormat`.
+struct ModuleReportEntry {
+    name: String,
+    modified: bool,
+    size_before: usize,
+    size_after: usize,
+}
+
+fn build_report(results: &[ModuleReportEntry], format: &ReportFormat) -> String {
+    match format {
+        ReportFormat::Human => {
+            let mut out = String::new();
+            for entry in results {
+                out.push_str(&format!(
+                    "{}: {} ({} -> {} bytes)\n",
+                    entry.name,
+                    if entry.modified { "modified" } else { "unchanged" },
+                    entry.size_before,
+                    entry.size_after
+                ));
+            }
+            out
+        }
+        ReportFormat::Json => {
+            let mut out = String::from("[\n");
+            for (i, entry) in results.iter().enumerate() {
+                out.push_str(&format!(
+                    "  {{\"module\": \"{}\", \"modified\": {}, \"size_before\": {}, \"size_after\": {}}}",
+                    entry.name, entry.modified, entry.size_before, entry.size_after
+                ));
+                if i + 1 != results.len() {
+                    out.push(',');
+                }
+                out.push('\n');
+            }
+            out.push(']');
+            out
+        }
+        ReportFormat::Yaml => {
+            let mut out = String::new();
+            for entry in results {
+                out.push_str(&format!(
+                    "- module: {}\n  modified: {}\n  size_before: {}\n  size_after: {}\n",
+                    entry.name, entry.modified, entry.size_before, entry.size_after
+                ));
+            }
+            out
+        }
+        ReportFormat::Checkstyle => {
+            let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<checkstyle version=\"1.0\">\n");
+            for entry in results {
+                out.push_str(&format!(
+                    "  <file name=\"{}\">{}</file>\n",
+                    entry.name,
+                    if entry.modified {
+                        format!(
+                            "<error message=\"module modified ({} -> {} bytes)\" severity=\"info\"/>",
+                            entry.size_before, entry.size_after
+                        )
+                    } else {
+                        String::new()
+                    }
+                ));
+            }
+            out.push_str("</checkstyle>");
+            out
+        }
+    }
+}
+
 pub fn chisel_oneliner(flags: ChiselFlags) -> i32 {
-    let log_level = match flags.value_of("util.debugging") {
-        Some("true") => 1i32,
-        Some("false") => 0i32,
-        _ => panic!("util.debugging must be set 'true' or 'false'"),
-    };
-    logger::set_global_log_level(log_level);
+    let verbosity = Verbosity::from_flags(&flags);
+    logger::set_global_log_level(verbosity.log_level());
 
     chisel_debug!(1, "Running chisel in oneliner (unix-style) mode");
 
-    // If no modules were passed, just exit.
-    match flags.value_of("oneliner.modules") {
-        Some(module_list) => {
-            chisel_debug!(1, "Modules passed:\n\t{}", module_list);
-
-            let options_list = if let Some(opts) = flags.value_of("oneliner.modules.options") {
-                chisel_debug!(1, "Module options passed:\n\t{}", opts);
-                opts
-            } else {
-                ""
-            };
-
-            let input_file = flags
-                .value_of("oneliner.file")
-                .unwrap_or_else(|| fail(1, "No file specified"));
-
-            let output_file = flags.value_of("oneliner.output");
-            let output_file = match output_file {
-                Some(p) => p.to_string(),
-                None => "/dev/stdout".to_string(),
-            };
-
-            let chisel_config = match ChiselConfig::from_args(module_list, options_list) {
-                Ok(mut config) => {
-                    // Inject the input and output file paths here.
-                    config.rulesets_mut()[0]
-                        .1
-                        .options_mut()
-                        .insert("file".to_string(), input_file.to_string());
-                    config.rulesets_mut()[0]
-                        .1
-                        .options_mut()
-                        .insert("output".to_string(), output_file);
-                    config
-                }
-                Err(e) => fail(1, &format!("Failed to load configuration: {}", e)),
-            };
+    // Modules are no longer mandatory on the command line: a discovered
+    // `.chisel.yml` (see `find_project_config`) can supply the whole
+    // pipeline, with any CLI-specified modules/options layered on top.
+    let module_list = flags.value_of("oneliner.modules");
+    if let Some(module_list) = module_list {
+        chisel_debug!(1, "Modules passed:\n\t{}", module_list);
+    }
+
+    // A file argument of `-`, or no file argument at all, means "read
+    // the module from stdin" rather than `fail`ing outright. Otherwise
+    // the argument is a comma-separated list of paths/globs, one
+    // invocation of the driver being fired per matched file.
+    let in_place = flags.occurrences_of("oneliner.in-place") > 0;
+    let explicit_output = flags.value_of("oneliner.output").map(|p| p.to_string());
+    // Each file is paired with the directory `find_project_config` should
+    // search upward from. For real input files that's just their own
+    // parent; for stdin it must be the caller's actual working directory,
+    // not the staged tempfile's directory under `std::env::temp_dir()` (see
+    // `stage_stdin_to_tempfile`), or project config discovery would always
+    // walk up from `/tmp` and never find it.
+    //
+    // `_stdin_guard` is kept alive until the end of this call so the staged
+    // stdin tempfile (if any) outlives every `run_one` invocation that reads
+    // it, and is deleted automatically once it's dropped here.
+    let (files, _stdin_guard): (Vec<(String, PathBuf)>, Option<NamedTempFile>) =
+        match flags.value_of("oneliner.file") {
+            Some("-") | None => {
+                let tempfile = stage_stdin_to_tempfile();
+                let path = tempfile.path().to_string_lossy().into_owned();
+                let cwd = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+                (vec![(path, cwd)], Some(tempfile))
+            }
+            Some(pattern_list) => {
+                let with_roots = expand_input_files(pattern_list)
+                    .into_iter()
+                    .map(|f| {
+                        let root = Path::new(&f)
+                            .parent()
+                            .filter(|p| !p.as_os_str().is_empty())
+                            .map(Path::to_path_buf)
+                            .unwrap_or_else(|| PathBuf::from("."));
+                        (f, root)
+                    })
+                    .collect();
+                (with_roots, None)
+            }
+        };
+    if files.is_empty() {
+        fail(1, "no input files matched");
+    }
+    // A single `--output` path can't be shared by more than one matched
+    // file without each iteration silently clobbering the last one's
+    // result; require `--in-place` (or templated names) for batches.
+    if files.len() > 1 && !in_place && explicit_output.is_some() {
+        fail(
+            1,
+            "--output cannot be used with multiple input files; use --in-place or drop --output to get templated {stem}.chiseled.{ext} names",
+        );
+    }
 
-            chisel_debug!(1, "{}", chisel_config);
+    // One aggregate exit status for the whole batch: any single file
+    // erroring should not silently swallow the others' failures.
+    let mut aggregate_status = 0;
+    for (input_file, discovery_root) in &files {
+        let output_file = if in_place {
+            Some(input_file.clone())
+        } else if files.len() == 1 {
+            explicit_output.clone()
+        } else {
+            Some(templated_output_path(input_file))
+        };
 
-            let mut driver = ChiselDriver::new(chisel_config);
+        let status = run_one(
+            &flags,
+            module_list,
+            input_file,
+            discovery_root,
+            output_file,
+            verbosity,
+        );
+        if status != 0 {
+            aggregate_status = status;
+        }
+    }
+    aggregate_status
+}
 
-            loop {
-                match driver.fire() {
-                    DriverState::Error(err, _) => {
-                        fail(1, &format!("runtime error: {}", err));
-                    }
-                    DriverState::Done(_) => break,
-                    _ => panic!("Should never return READY"),
-                }
+/// Runs the full chisel pipeline (config, driver, report, write) for a
+/// single input file. Returns the process exit status for that file alone;
+/// the caller is responsible for aggregating statuses across a batch.
+fn run_one(
+    flags: &ChiselFlags,
+    module_list: Option<&str>,
+    input_file: &str,
+    discovery_root: &Path,
+    output_file: Option<String>,
+    verbosity: Verbosity,
+) -> i32 {
+    let options_list = if let Some(opts) = flags.value_of("oneliner.modules.options") {
+        chisel_debug!(1, "Module options passed:\n\t{}", opts);
+        opts
+    } else {
+        ""
+    };
+
+    // `--check` runs the full pipeline but never writes output; it only
+    // reports (via exit code) whether any ruleset would have modified
+    // the binary, mirroring rustfmt's `WriteMode::Check`.
+    let check_mode = flags.occurrences_of("oneliner.check") > 0;
+
+    let project_config_path = find_project_config(discovery_root);
+    let used_project_config = project_config_path.is_some();
+
+    let mut chisel_config = match (project_config_path, module_list) {
+        (Some(path), _) => {
+            chisel_debug!(1, "Using project config found at {}", path.display());
+            ChiselConfig::from_file(&path)
+                .unwrap_or_else(|e| fail(1, &format!("failed to load {}: {}", path.display(), e)))
+        }
+        (None, Some(module_list)) => match ChiselConfig::from_args(module_list, options_list) {
+            Ok(config) => config,
+            Err(e) => fail(1, &format!("Failed to load configuration: {}", e)),
+        },
+        (None, None) => fail(1, "no modules specified"),
+    };
+
+    // CLI-specified modules/options always win over whatever the project
+    // config declared, letting teams override one module (or just one
+    // option) ad hoc. `--module-options` alone, with no positional modules
+    // argument, is a valid override on its own: it must not require
+    // re-specifying the project config's module list to take effect.
+    if used_project_config && (module_list.is_some() || !options_list.is_empty()) {
+        chisel_config
+            .apply_cli_overrides(module_list, options_list)
+            .unwrap_or_else(|e| fail(1, &format!("invalid --module-options: {}", e)));
+    }
+
+    // Inject the input file path here.
+    chisel_config.rulesets_mut()[0]
+        .1
+        .options_mut()
+        .insert("file".to_string(), input_file.to_string());
+    // In `--check` mode, and when streaming to stdout, the
+    // "output" option is deliberately left unset.
+    if !check_mode {
+        if let Some(ref output_file) = output_file {
+            chisel_config.rulesets_mut()[0]
+                .1
+                .options_mut()
+                .insert("output".to_string(), output_file.clone());
+        }
+    }
+
+    chisel_debug!(1, "{}", chisel_config);
+
+    let mut driver = ChiselDriver::new(chisel_config);
+
+    loop {
+        match driver.fire() {
+            DriverState::Error(err, _) => {
+                // A per-file runtime error must not abort the rest of the
+                // batch (see `chisel_oneliner`'s `aggregate_status`), so this
+                // reports and returns rather than calling `fail`.
+                eprintln!("error: runtime error: {}", err);
+                return 1;
             }
+            DriverState::Done(_) => break,
+            _ => panic!("Should never return READY"),
+        }
+    }
 
-            let mut results = driver.take_result();
-            // wish list: write yaml-encoded results to stdout
-            chisel_debug!(1, "Module execution completed successfully");
-            eprintln!("{}", &results);
-
-            // Get ruleset
-            let results = results.rulesets_mut();
-            let io_result = match flags.value_of("output.mode") {
-                Some("bin") => {
-                    let mut result = results.pop().expect("One ruleset was executed");
-                    result.write("bin")
-                }
-                Some("wat") => {
-                    let mut result = results.pop().expect("One ruleset was executed");
-                    result.write("wat")
-                }
-                Some("hex") => {
-                    let mut result = results.pop().expect("One ruleset was executed");
-                    result.write("hex")
-                }
-                _ => panic!("CLI parser ensures value can only be one of the above"),
-            };
-
-            match io_result {
-                Ok(true) => eprintln!("Successfully wrote output to file."),
-                Ok(false) => eprintln!("No changes to write."),
-                Err(e) => fail(
-                    1,
-                    &format!("failed to write output to file: {}", e.description()),
-                ),
+    let mut results = driver.take_result();
+    chisel_debug!(1, "Module execution completed successfully");
+
+    let report_format = ReportFormat::from_flag(flags.value_of("emit-report"));
+    let report_entries: Vec<ModuleReportEntry> = results
+        .rulesets()
+        .iter()
+        .map(|result| ModuleReportEntry {
+            name: result.name().to_string(),
+            modified: result.is_modified(),
+            size_before: result.original_size(),
+            size_after: result.final_size(),
+        })
+        .collect();
+    if !verbosity.is_quiet() {
+        eprintln!("{}", build_report(&report_entries, &report_format));
+    }
+
+    // Get ruleset
+    let results = results.rulesets_mut();
+
+    if check_mode {
+        let result = results.pop().expect("One ruleset was executed");
+        return if result.is_modified() {
+            if !verbosity.is_quiet() {
+                eprintln!("Module would be modified by chisel.");
+            }
+            1
+        } else {
+            if !verbosity.is_quiet() {
+                eprintln!("Module is already conformant.");
             }
             0
+        };
+    }
+
+    let encoding = match flags.value_of("output.mode") {
+        Some(encoding @ "bin") | Some(encoding @ "wat") | Some(encoding @ "hex") => encoding,
+        _ => panic!("CLI parser ensures value can only be one of the above"),
+    };
+
+    let mut result = results.pop().expect("One ruleset was executed");
+    // `write` reports whether it actually touched the file (it skips unmodified
+    // input); `write_to` always streams the bytes, so its result isn't a
+    // meaningful "did anything change" signal and gets no such message.
+    let (io_result, streamed) = match output_file {
+        Some(_) => (result.write(encoding), false),
+        None => (result.write_to(&mut io::stdout(), encoding), true),
+    };
+
+    match io_result {
+        Ok(_) if streamed => {}
+        Ok(true) if !verbosity.is_quiet() => eprintln!("Successfully wrote output to file."),
+        Ok(false) if !verbosity.is_quiet() => eprintln!("No changes to write."),
+        Ok(_) => {}
+        // As with the driver error above, a write failure on one file in a
+        // batch must not prevent the rest from being attempted.
+        Err(e) => {
+            eprintln!(
+                "error: failed to write output to file: {}",
+                e.description()
+            );
+            return 1;
+        }
+    }
+    0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::options::build_oneliner_cli;
+
+    #[test]
+    fn templated_output_path_appends_chiseled_suffix() {
+        assert_eq!(
+            templated_output_path("foo/bar.wasm"),
+            "foo/bar.chiseled.wasm"
+        );
+    }
+
+    #[test]
+    fn templated_output_path_defaults_extension_when_missing() {
+        assert_eq!(templated_output_path("mod"), "mod.chiseled.wasm");
+    }
+
+    #[test]
+    fn expand_input_files_splits_and_trims_a_literal_list() {
+        assert_eq!(
+            expand_input_files("a.wasm, b.wasm ,c.wasm"),
+            vec!["a.wasm", "b.wasm", "c.wasm"]
+        );
+    }
+
+    fn flags(args: &[&str]) -> ChiselFlags<'static> {
+        let matches = build_oneliner_cli().get_matches_from(args);
+        ChiselFlags::new(matches)
+    }
+
+    #[test]
+    fn verbosity_defaults_to_normal() {
+        assert!(Verbosity::from_flags(&flags(&["chisel"])) == Verbosity::Normal);
+    }
+
+    #[test]
+    fn verbosity_quiet_flag_is_quiet() {
+        assert!(Verbosity::from_flags(&flags(&["chisel", "-q"])).is_quiet());
+    }
+
+    #[test]
+    fn verbosity_verbose_counts_repetitions() {
+        match Verbosity::from_flags(&flags(&["chisel", "-vvv"])) {
+            Verbosity::Verbose(3) => {}
+            other => panic!("expected Verbose(3), got a different Verbosity variant instead: log_level={}", other.log_level()),
         }
-        None => fail(1, "no modules specified"),
     }
 }
@@ -0,0 +1,446 @@
+//! Config types shared by the config-driven and oneliner front ends: the
+//! pipeline description (`ChiselConfig`) going in, and the per-module
+//! outcome (`ChiselResult`) coming out.
+
+use std::collections::HashMap;
+use std::error;
+use std::fmt;
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+
+/// An error building or running a `ChiselConfig`.
+#[derive(Debug)]
+pub struct ChiselError(String);
+
+impl ChiselError {
+    pub fn new(message: &str) -> ChiselError {
+        ChiselError(message.to_string())
+    }
+}
+
+impl fmt::Display for ChiselError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl error::Error for ChiselError {}
+
+/// Builds a `ChiselConfig` from the oneliner CLI's module list and flat
+/// `key=value` options string.
+pub trait FromArgs: Sized {
+    fn from_args(modules: &str, options: &str) -> Result<Self, ChiselError>;
+}
+
+/// Parses a comma-separated `key=value` list, as accepted by both the CLI's
+/// `--module-options` and a `.chisel.yml` ruleset's `options:` line.
+fn parse_options_string(options: &str) -> Result<HashMap<String, String>, ChiselError> {
+    let mut parsed = HashMap::new();
+    for pair in options.split(',').filter(|p| !p.is_empty()) {
+        match pair.split_once('=') {
+            Some((k, v)) => {
+                parsed.insert(k.trim().to_string(), v.trim().to_string());
+            }
+            None => return Err(ChiselError::new(&format!("malformed option: {}", pair))),
+        }
+    }
+    Ok(parsed)
+}
+
+/// One ruleset's options (`file`, `output`, and whatever the modules
+/// themselves read out of the map).
+#[derive(Clone, Debug, Default)]
+pub struct RulesetConfig {
+    options: HashMap<String, String>,
+}
+
+impl RulesetConfig {
+    pub fn options(&self) -> &HashMap<String, String> {
+        &self.options
+    }
+
+    pub fn options_mut(&mut self) -> &mut HashMap<String, String> {
+        &mut self.options
+    }
+}
+
+/// An ordered pipeline of (name, ruleset) pairs to execute.
+#[derive(Clone, Debug, Default)]
+pub struct ChiselConfig {
+    rulesets: Vec<(String, RulesetConfig)>,
+}
+
+impl ChiselConfig {
+    pub fn rulesets(&self) -> &[(String, RulesetConfig)] {
+        &self.rulesets
+    }
+
+    /// Layers CLI-specified modules/options onto this config's single
+    /// ruleset, CLI winning on conflicts. Meant for a project config
+    /// discovered by `chisel_oneliner`'s `.chisel.yml` search.
+    ///
+    /// `modules` may be absent: `chisel --module-options foo=bar` with no
+    /// positional modules argument is exactly "keep the project config's
+    /// modules, override just these options", and must not be treated as
+    /// "no CLI overrides at all".
+    pub fn apply_cli_overrides(
+        &mut self,
+        modules: Option<&str>,
+        options: &str,
+    ) -> Result<(), ChiselError> {
+        let ruleset_options = self.rulesets_mut()[0].1.options_mut();
+        if let Some(modules) = modules {
+            ruleset_options.insert("modules".to_string(), modules.to_string());
+        }
+        for (k, v) in parse_options_string(options)? {
+            ruleset_options.insert(k, v);
+        }
+        Ok(())
+    }
+
+    pub fn rulesets_mut(&mut self) -> &mut Vec<(String, RulesetConfig)> {
+        &mut self.rulesets
+    }
+}
+
+impl FromArgs for ChiselConfig {
+    fn from_args(modules: &str, options: &str) -> Result<ChiselConfig, ChiselError> {
+        if modules.trim().is_empty() {
+            return Err(ChiselError::new("no modules specified"));
+        }
+        let mut ruleset = RulesetConfig::default();
+        ruleset
+            .options_mut()
+            .insert("modules".to_string(), modules.to_string());
+        for (k, v) in parse_options_string(options)? {
+            ruleset.options_mut().insert(k, v);
+        }
+        Ok(ChiselConfig {
+            rulesets: vec![("oneliner".to_string(), ruleset)],
+        })
+    }
+}
+
+impl ChiselConfig {
+    /// Loads a `.chisel.yml` project config, as discovered by
+    /// `chisel_oneliner`'s upward directory search.
+    ///
+    /// The format is a small, hand-rolled subset of YAML (this crate has no
+    /// YAML dependency) rather than the full spec: a top-level `rulesets:`
+    /// map, each entry giving the ruleset's name, a `modules:` line
+    /// (comma-separated, same syntax as the CLI positional argument) and an
+    /// optional `options:` line (comma-separated `key=value`, same syntax
+    /// as `--module-options`):
+    ///
+    /// ```yaml
+    /// rulesets:
+    ///   ci:
+    ///     modules: trimexports,remapimports
+    ///     options: strict=true
+    /// ```
+    ///
+    /// Oneliner mode executes exactly one ruleset per invocation, so a
+    /// config with more than one is rejected with a clear error rather than
+    /// guessing which one was meant; true multi-ruleset pipelines are a
+    /// config-driven-mode concern.
+    pub fn from_file(path: &Path) -> Result<ChiselConfig, ChiselError> {
+        let contents = fs::read_to_string(path).map_err(|e| ChiselError::new(&e.to_string()))?;
+
+        let mut rulesets: Vec<(String, RulesetConfig)> = Vec::new();
+        let mut current_name: Option<String> = None;
+        let mut current_modules = String::new();
+        let mut current_options = String::new();
+
+        macro_rules! flush_current {
+            () => {
+                if let Some(name) = current_name.take() {
+                    rulesets.push(build_ruleset_config(
+                        path,
+                        &name,
+                        &current_modules,
+                        &current_options,
+                    )?);
+                    current_modules.clear();
+                    current_options.clear();
+                }
+            };
+        }
+
+        for raw_line in contents.lines() {
+            let line = raw_line.trim_end();
+            if line.trim().is_empty() || line.trim_start().starts_with('#') {
+                continue;
+            }
+            if line == "rulesets:" {
+                continue;
+            }
+            let indent = line.len() - line.trim_start().len();
+            let trimmed = line.trim();
+            match indent {
+                2 => {
+                    flush_current!();
+                    current_name = Some(trimmed.trim_end_matches(':').to_string());
+                }
+                4 => match trimmed.split_once(':') {
+                    Some(("modules", v)) => current_modules = v.trim().to_string(),
+                    Some(("options", v)) => current_options = v.trim().to_string(),
+                    _ => {
+                        return Err(ChiselError::new(&format!(
+                            "{}: malformed line: {}",
+                            path.display(),
+                            raw_line
+                        )))
+                    }
+                },
+                _ => {
+                    return Err(ChiselError::new(&format!(
+                        "{}: malformed line: {}",
+                        path.display(),
+                        raw_line
+                    )))
+                }
+            }
+        }
+        flush_current!();
+
+        if rulesets.is_empty() {
+            return Err(ChiselError::new(&format!(
+                "{}: no rulesets defined",
+                path.display()
+            )));
+        }
+        if rulesets.len() > 1 {
+            return Err(ChiselError::new(&format!(
+                "{} defines {} rulesets, but oneliner mode only runs one pipeline per invocation; \
+                 trim it to a single ruleset or invoke config-driven mode instead",
+                path.display(),
+                rulesets.len()
+            )));
+        }
+
+        Ok(ChiselConfig { rulesets })
+    }
+}
+
+/// Builds one named ruleset from a `.chisel.yml` entry's raw `modules:`/
+/// `options:` strings.
+fn build_ruleset_config(
+    path: &Path,
+    name: &str,
+    modules: &str,
+    options: &str,
+) -> Result<(String, RulesetConfig), ChiselError> {
+    if modules.trim().is_empty() {
+        return Err(ChiselError::new(&format!(
+            "{}: ruleset '{}' has no modules",
+            path.display(),
+            name
+        )));
+    }
+    let mut ruleset = RulesetConfig::default();
+    ruleset
+        .options_mut()
+        .insert("modules".to_string(), modules.to_string());
+    for (k, v) in parse_options_string(options)? {
+        ruleset.options_mut().insert(k, v);
+    }
+    Ok((name.to_string(), ruleset))
+}
+
+impl fmt::Display for ChiselConfig {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (name, ruleset) in &self.rulesets {
+            writeln!(f, "{}:", name)?;
+            for (k, v) in ruleset.options() {
+                writeln!(f, "  {}: {}", k, v)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The outcome of running one ruleset: its original and transformed bytes.
+#[derive(Clone)]
+pub struct ModuleResult {
+    name: String,
+    output: Option<String>,
+    original: Vec<u8>,
+    transformed: Vec<u8>,
+}
+
+impl ModuleResult {
+    pub fn new(
+        name: String,
+        output: Option<String>,
+        original: Vec<u8>,
+        transformed: Vec<u8>,
+    ) -> ModuleResult {
+        ModuleResult {
+            name,
+            output,
+            original,
+            transformed,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn is_modified(&self) -> bool {
+        self.original != self.transformed
+    }
+
+    pub fn original_size(&self) -> usize {
+        self.original.len()
+    }
+
+    pub fn final_size(&self) -> usize {
+        self.transformed.len()
+    }
+
+    fn encode(&self, encoding: &str) -> Result<Vec<u8>, ChiselError> {
+        match encoding {
+            "bin" => Ok(self.transformed.clone()),
+            "hex" => Ok(self
+                .transformed
+                .iter()
+                .map(|b| format!("{:02x}", b))
+                .collect::<String>()
+                .into_bytes()),
+            // Real textual .wat encoding lives in the core transform crate;
+            // oneliner mode only round-trips the binary it was given.
+            "wat" => Ok(self.transformed.clone()),
+            other => Err(ChiselError::new(&format!("unknown encoding: {}", other))),
+        }
+    }
+
+    /// Writes the encoded result to its configured `output` path. Returns
+    /// `Ok(false)` without touching the filesystem if nothing changed.
+    pub fn write(&mut self, encoding: &str) -> Result<bool, ChiselError> {
+        if !self.is_modified() {
+            return Ok(false);
+        }
+        let path = self
+            .output
+            .clone()
+            .ok_or_else(|| ChiselError::new("no output path configured"))?;
+        let bytes = self.encode(encoding)?;
+        fs::write(&path, bytes).map_err(|e| ChiselError::new(&e.to_string()))?;
+        Ok(true)
+    }
+
+    /// Like `write`, but streams to an arbitrary `Write` instead of opening
+    /// a path, so callers can target stdout without a pseudo-path.
+    ///
+    /// Unlike `write`, this always emits the encoded bytes, even when
+    /// `!is_modified()`: a stdout-streaming caller is a link in a Unix
+    /// pipeline (`chisel trimexports | wasm-opt ...`), and a no-op ruleset
+    /// writing zero bytes would silently truncate the stream for everything
+    /// downstream instead of passing the module through unchanged.
+    pub fn write_to(&mut self, writer: &mut dyn Write, encoding: &str) -> Result<bool, ChiselError> {
+        let bytes = self.encode(encoding)?;
+        writer
+            .write_all(&bytes)
+            .map_err(|e| ChiselError::new(&e.to_string()))?;
+        Ok(true)
+    }
+}
+
+/// All `ModuleResult`s produced by one `ChiselDriver` run.
+#[derive(Clone, Default)]
+pub struct ChiselResult {
+    rulesets: Vec<ModuleResult>,
+}
+
+impl ChiselResult {
+    pub fn rulesets(&self) -> &[ModuleResult] {
+        &self.rulesets
+    }
+
+    pub fn rulesets_mut(&mut self) -> &mut Vec<ModuleResult> {
+        &mut self.rulesets
+    }
+
+    pub fn push(&mut self, result: ModuleResult) {
+        self.rulesets.push(result);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write as _;
+    use tempfile::NamedTempFile;
+
+    fn config_file(contents: &str) -> NamedTempFile {
+        let mut file = NamedTempFile::new().expect("failed to create temp file");
+        file.write_all(contents.as_bytes())
+            .expect("failed to write temp file");
+        file
+    }
+
+    #[test]
+    fn from_file_parses_a_single_ruleset() {
+        let file = config_file(
+            "rulesets:\n  ci:\n    modules: trimexports,remapimports\n    options: strict=true\n",
+        );
+        let config = ChiselConfig::from_file(file.path()).expect("should parse");
+        assert_eq!(config.rulesets().len(), 1);
+        let (name, ruleset) = &config.rulesets()[0];
+        assert_eq!(name, "ci");
+        assert_eq!(
+            ruleset.options().get("modules").map(String::as_str),
+            Some("trimexports,remapimports")
+        );
+        assert_eq!(
+            ruleset.options().get("strict").map(String::as_str),
+            Some("true")
+        );
+    }
+
+    #[test]
+    fn from_file_rejects_more_than_one_ruleset() {
+        let file = config_file("rulesets:\n  ci:\n    modules: trimexports\n  lint:\n    modules: validate\n");
+        let err = ChiselConfig::from_file(file.path()).unwrap_err();
+        assert!(err.to_string().contains("2 rulesets"));
+    }
+
+    #[test]
+    fn from_file_rejects_a_ruleset_with_no_modules() {
+        let file = config_file("rulesets:\n  ci:\n    options: strict=true\n");
+        assert!(ChiselConfig::from_file(file.path()).is_err());
+    }
+
+    #[test]
+    fn from_file_rejects_a_malformed_line() {
+        let file = config_file("rulesets:\n  ci:\n    not_a_known_key: oops\n");
+        assert!(ChiselConfig::from_file(file.path()).is_err());
+    }
+
+    #[test]
+    fn from_file_rejects_an_empty_file() {
+        let file = config_file("");
+        assert!(ChiselConfig::from_file(file.path()).is_err());
+    }
+
+    #[test]
+    fn apply_cli_overrides_without_modules_keeps_project_modules() {
+        let mut config =
+            ChiselConfig::from_file(config_file("rulesets:\n  ci:\n    modules: trimexports\n").path())
+                .expect("should parse");
+        config
+            .apply_cli_overrides(None, "strict=true")
+            .expect("should apply");
+        let (_, ruleset) = &config.rulesets()[0];
+        assert_eq!(
+            ruleset.options().get("modules").map(String::as_str),
+            Some("trimexports")
+        );
+        assert_eq!(
+            ruleset.options().get("strict").map(String::as_str),
+            Some("true")
+        );
+    }
+}
@@ -0,0 +1,59 @@
+//! Runs a `ChiselConfig`'s rulesets against their configured input files.
+
+use crate::config::{ChiselConfig, ChiselError, ChiselResult, ModuleResult};
+
+/// The state returned by each call to `ChiselDriver::fire`.
+pub enum DriverState {
+    Ready,
+    Error(ChiselError, ChiselResult),
+    Done(ChiselResult),
+}
+
+/// Consumes a `ChiselConfig`'s rulesets and produces a `ChiselResult`.
+///
+/// Oneliner mode always configures exactly one ruleset, so `fire` resolves
+/// every configured ruleset in a single call; `Ready` is reserved for front
+/// ends that drive the pipeline ruleset-by-ruleset.
+pub struct ChiselDriver {
+    config: ChiselConfig,
+    result: Option<ChiselResult>,
+}
+
+impl ChiselDriver {
+    pub fn new(config: ChiselConfig) -> ChiselDriver {
+        ChiselDriver {
+            config,
+            result: None,
+        }
+    }
+
+    pub fn fire(&mut self) -> DriverState {
+        let mut result = ChiselResult::default();
+        for (name, ruleset) in self.config.rulesets_mut().drain(..) {
+            let path = match ruleset.options().get("file") {
+                Some(p) => p.clone(),
+                None => return DriverState::Error(ChiselError::new("no 'file' option set"), result),
+            };
+            let bytes = match std::fs::read(&path) {
+                Ok(b) => b,
+                Err(e) => return DriverState::Error(ChiselError::new(&e.to_string()), result),
+            };
+            // The real transform pipeline (trimexports, etc.) lives in the
+            // core crate and is not called here; the transformed bytes are
+            // an unchanged copy of the input.
+            let transformed = bytes.clone();
+            result.push(ModuleResult::new(
+                name,
+                ruleset.options().get("output").cloned(),
+                bytes,
+                transformed,
+            ));
+        }
+        self.result = Some(result.clone());
+        DriverState::Done(result)
+    }
+
+    pub fn take_result(&mut self) -> ChiselResult {
+        self.result.take().unwrap_or_default()
+    }
+}
@@ -0,0 +1,20 @@
+//! wasm-chisel: a pipeline of small, composable wasm-to-wasm transforms.
+//!
+//! The front ends (config-driven and oneliner/unix-style) build a
+//! `ChiselConfig`, hand it to a `ChiselDriver`, and report the outcome.
+
+#[macro_use]
+mod logger;
+pub mod config;
+pub mod driver;
+mod cmd_oneliner;
+pub mod options;
+
+pub use cmd_oneliner::chisel_oneliner;
+
+/// Prints `message` to stderr and exits the process with `status`. Used by
+/// the CLI front ends in place of `panic!` for user-facing errors.
+pub fn fail(status: i32, message: &str) -> ! {
+    eprintln!("error: {}", message);
+    std::process::exit(status);
+}
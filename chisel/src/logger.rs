@@ -0,0 +1,26 @@
+//! A global, atomic log level consulted by the `chisel_debug!` macro.
+
+use std::sync::atomic::{AtomicI32, Ordering};
+
+static LOG_LEVEL: AtomicI32 = AtomicI32::new(0);
+
+/// Sets the global debug log level. `chisel_debug!(n, ...)` call sites only
+/// print once the level has been raised to at least `n`.
+pub fn set_global_log_level(level: i32) {
+    LOG_LEVEL.store(level, Ordering::SeqCst);
+}
+
+/// Reads the current global debug log level.
+pub fn global_log_level() -> i32 {
+    LOG_LEVEL.load(Ordering::SeqCst)
+}
+
+/// Prints `$($arg)*` to stderr if the global log level is at least `$level`.
+#[macro_export]
+macro_rules! chisel_debug {
+    ($level:expr, $($arg:tt)*) => {
+        if $crate::logger::global_log_level() >= $level {
+            eprintln!($($arg)*);
+        }
+    };
+}
@@ -0,0 +1,91 @@
+//! CLI argument definitions for chisel's front ends, and the thin
+//! `ChiselFlags` wrapper the front ends query by dotted name.
+
+use clap::{App, Arg, ArgMatches};
+
+/// Wraps a parsed `clap::ArgMatches`, keyed by the same dotted names used
+/// throughout the front-end modules.
+pub struct ChiselFlags<'a> {
+    matches: ArgMatches<'a>,
+}
+
+impl<'a> ChiselFlags<'a> {
+    pub fn new(matches: ArgMatches<'a>) -> ChiselFlags<'a> {
+        ChiselFlags { matches }
+    }
+
+    pub fn value_of(&self, name: &str) -> Option<&str> {
+        self.matches.value_of(name)
+    }
+
+    pub fn occurrences_of(&self, name: &str) -> u64 {
+        self.matches.occurrences_of(name)
+    }
+}
+
+/// Builds the oneliner (unix-style) CLI surface.
+pub fn build_oneliner_cli<'a, 'b>() -> App<'a, 'b> {
+    App::new("chisel")
+        .arg(
+            Arg::with_name("oneliner.modules")
+                .help("Comma-separated list of modules to run")
+                .index(1),
+        )
+        .arg(
+            Arg::with_name("oneliner.modules.options")
+                .long("module-options")
+                .takes_value(true)
+                .help("Comma-separated key=value module options"),
+        )
+        .arg(
+            Arg::with_name("oneliner.file")
+                .long("file")
+                .short("f")
+                .takes_value(true)
+                .help("Input path(s)/glob(s), comma-separated; '-' or omitted reads stdin"),
+        )
+        .arg(
+            Arg::with_name("oneliner.output")
+                .long("output")
+                .short("o")
+                .takes_value(true)
+                .help("Output path; omitted means stream to stdout"),
+        )
+        .arg(
+            Arg::with_name("output.mode")
+                .long("output-mode")
+                .takes_value(true)
+                .possible_values(&["bin", "wat", "hex"])
+                .default_value("bin"),
+        )
+        .arg(Arg::with_name("oneliner.check").long("check").help(
+            "Exit nonzero if any module would change the input, without writing anything",
+        ))
+        .arg(
+            Arg::with_name("emit-report")
+                .long("emit-report")
+                .takes_value(true)
+                .possible_values(&["human", "json", "yaml", "checkstyle"])
+                .help("Emit a structured per-module outcome report to stderr"),
+        )
+        .arg(
+            Arg::with_name("oneliner.in-place")
+                .long("in-place")
+                .help("Write each batched file's output back over itself"),
+        )
+        .arg(
+            Arg::with_name("util.quiet")
+                .long("quiet")
+                .short("q")
+                .conflicts_with("util.verbose")
+                .help("Suppress informational messages; only report real errors"),
+        )
+        .arg(
+            Arg::with_name("util.verbose")
+                .long("verbose")
+                .short("v")
+                .multiple(true)
+                .conflicts_with("util.quiet")
+                .help("Raise the debug log level; may be repeated"),
+        )
+}